@@ -0,0 +1,293 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Bytes, BytesMut};
+use futures::Stream;
+use pin_project::pin_project;
+
+use crate::stream::{poll_read_chunk, BufferSize};
+use crate::RangeBody;
+
+// default upper bound on the backing buffer each part's body is carved out of,
+// matching `stream::POOL_BUFFER_SIZE`.
+const POOL_BUFFER_SIZE: usize = 256 * 1024;
+
+/// A single `(start, length)` segment of a multi-range request.
+#[derive(Clone, Copy, Debug)]
+pub struct RangeSegment {
+    pub start: u64,
+    pub length: u64,
+}
+
+/// Error returned when a [`MultiRangeStream`] is constructed with an invalid
+/// segment list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultiRangeError {
+    /// The segment list was empty.
+    NoSegments,
+    /// A segment had zero length, which cannot be framed as a body part.
+    EmptySegment,
+}
+
+impl std::fmt::Display for MultiRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultiRangeError::NoSegments => f.write_str("multi-range request has no segments"),
+            MultiRangeError::EmptySegment => f.write_str("multi-range segment has zero length"),
+        }
+    }
+}
+
+impl std::error::Error for MultiRangeError {}
+
+/// Stream that serves a `multipart/byteranges` response for an ordered list of
+/// range segments over a single seekable [`RangeBody`].
+///
+/// Each part is framed with a MIME boundary, a `Content-Type` and a
+/// `Content-Range` header, followed by the segment's body bytes; the stream is
+/// terminated by the closing boundary. Seeking between segments reuses the
+/// body's [`RangeBody::start_seek`]/[`RangeBody::poll_complete`], and the
+/// per-segment read path is the shared [`poll_read_chunk`] helper so the
+/// single-range and multi-range streams stay in lockstep.
+#[pin_project]
+pub struct MultiRangeStream<B> {
+    state: MultiState,
+    // ordered segments and the index of the one currently being served
+    segments: Vec<RangeSegment>,
+    index: usize,
+    // framing inputs
+    boundary: String,
+    content_type: String,
+    total_size: u64,
+    // configured upper bound on each part's adaptive backing buffer
+    buffer_size: usize,
+    #[pin]
+    body: B,
+}
+
+impl<B: RangeBody> MultiRangeStream<B> {
+    /// Build a multi-range stream. `content_type` is the type of the underlying
+    /// resource (echoed into every part header) and `total_size` is its full
+    /// byte length (the denominator of each `Content-Range`).
+    ///
+    /// Returns [`MultiRangeError`] if `segments` is empty or contains a
+    /// zero-length segment, rather than panicking while the response streams.
+    pub fn new(
+        body: B,
+        segments: Vec<RangeSegment>,
+        boundary: String,
+        content_type: String,
+        total_size: u64,
+    ) -> Result<Self, MultiRangeError> {
+        Self::with_buffer_size(
+            body,
+            segments,
+            boundary,
+            content_type,
+            total_size,
+            POOL_BUFFER_SIZE,
+        )
+    }
+
+    /// As [`MultiRangeStream::new`], but with a configurable per-part backing
+    /// buffer cap, mirroring [`crate::stream::RangedStream::with_buffer_size`].
+    pub fn with_buffer_size(
+        body: B,
+        segments: Vec<RangeSegment>,
+        boundary: String,
+        content_type: String,
+        total_size: u64,
+        buffer_size: usize,
+    ) -> Result<Self, MultiRangeError> {
+        if segments.is_empty() {
+            return Err(MultiRangeError::NoSegments);
+        }
+        if segments.iter().any(|s| s.length == 0) {
+            return Err(MultiRangeError::EmptySegment);
+        }
+
+        Ok(MultiRangeStream {
+            state: MultiState::Boundary,
+            segments,
+            index: 0,
+            boundary,
+            content_type,
+            total_size,
+            buffer_size,
+            body,
+        })
+    }
+
+    /// The value to set as the response `Content-Type` header.
+    pub fn response_content_type(&self) -> String {
+        format!("multipart/byteranges; boundary={}", self.boundary)
+    }
+
+    /// The exact number of bytes this stream will emit, suitable for the
+    /// response `Content-Length`: the sum of every part's header and body plus
+    /// the inter-part and closing boundaries.
+    pub fn content_length(&self) -> u64 {
+        let mut total = 0u64;
+        for (i, segment) in self.segments.iter().enumerate() {
+            total += part_header(
+                i == 0,
+                &self.boundary,
+                &self.content_type,
+                segment,
+                self.total_size,
+            )
+            .len() as u64;
+            total += segment.length;
+        }
+        total += closing_boundary(&self.boundary).len() as u64;
+        total
+    }
+}
+
+enum MultiState {
+    // emit the current part's boundary + headers
+    Boundary,
+    Seek { start: u64, remaining: u64 },
+    Seeking { remaining: u64 },
+    Reading {
+        buffer: BytesMut,
+        sizing: BufferSize,
+        remaining: u64,
+    },
+    // current segment drained; advance to the next one or finish
+    NextPart,
+    // closing boundary emitted, nothing left
+    Done,
+}
+
+impl<B: RangeBody> Stream for MultiRangeStream<B> {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<io::Result<Bytes>>> {
+        let mut this = self.project();
+
+        loop {
+            match this.state {
+                MultiState::Boundary => {
+                    let segment = this.segments[*this.index];
+                    let header = part_header(
+                        *this.index == 0,
+                        this.boundary,
+                        this.content_type,
+                        &segment,
+                        *this.total_size,
+                    );
+                    *this.state = MultiState::Seek {
+                        start: segment.start,
+                        remaining: segment.length,
+                    };
+                    return Poll::Ready(Some(Ok(Bytes::from(header))));
+                }
+
+                MultiState::Seek { start, remaining } => {
+                    let (start, remaining) = (*start, *remaining);
+                    match this.body.as_mut().start_seek(start) {
+                        Err(e) => return Poll::Ready(Some(Err(e))),
+                        Ok(()) => {
+                            *this.state = MultiState::Seeking { remaining };
+                        }
+                    }
+                }
+
+                MultiState::Seeking { remaining } => {
+                    let remaining = *remaining;
+                    match this.body.as_mut().poll_complete(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        Poll::Ready(Ok(())) => {
+                            let mut sizing = BufferSize::new(*this.buffer_size, remaining);
+                            let buffer = sizing.allocate();
+                            *this.state = MultiState::Reading { buffer, sizing, remaining };
+                        }
+                    }
+                }
+
+                MultiState::Reading { buffer, sizing, remaining } => {
+                    match poll_read_chunk(this.body.as_mut(), cx, buffer, sizing, remaining) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        // EOF or a fully-drained segment: move on to the next part
+                        Poll::Ready(Ok(None)) => *this.state = MultiState::NextPart,
+                        Poll::Ready(Ok(Some(chunk))) => return Poll::Ready(Some(Ok(chunk))),
+                    }
+                }
+
+                MultiState::NextPart => {
+                    *this.index += 1;
+                    if *this.index < this.segments.len() {
+                        *this.state = MultiState::Boundary;
+                    } else {
+                        *this.state = MultiState::Done;
+                        let closing = closing_boundary(this.boundary);
+                        return Poll::Ready(Some(Ok(Bytes::from(closing))));
+                    }
+                }
+
+                MultiState::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+// render the boundary + header block preceding a single part's body. every
+// part except the first is preceded by a CRLF that terminates the previous
+// part's body, per RFC 7233.
+fn part_header(
+    first: bool,
+    boundary: &str,
+    content_type: &str,
+    segment: &RangeSegment,
+    total_size: u64,
+) -> String {
+    let prefix = if first { "" } else { "\r\n" };
+    // segments are validated non-zero, so `length - 1` never underflows
+    let end = segment.start + segment.length - 1;
+    format!(
+        "{prefix}--{boundary}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Range: bytes {start}-{end}/{total_size}\r\n\
+         \r\n",
+        start = segment.start,
+    )
+}
+
+fn closing_boundary(boundary: &str) -> String {
+    format!("\r\n--{boundary}--\r\n")
+}
+
+// Responder wiring: turn the stream into a `206 Partial Content` response with
+// the `multipart/byteranges` content type and an exact `Content-Length`.
+mod responder {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::response::{IntoResponse, Response};
+    use http::{header, StatusCode};
+
+    impl<B> IntoResponse for MultiRangeStream<B>
+    where
+        B: RangeBody + Send + 'static,
+    {
+        fn into_response(self) -> Response {
+            let content_type = self.response_content_type();
+            let content_length = self.content_length();
+
+            Response::builder()
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_TYPE, content_type)
+                .header(header::CONTENT_LENGTH, content_length)
+                .header(header::ACCEPT_RANGES, "bytes")
+                .body(Body::from_stream(self))
+                .expect("response builder with valid header values")
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{io, mem};
+use std::io;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
@@ -9,24 +9,133 @@ use tokio::io::ReadBuf;
 
 use crate::RangeBody;
 
-const IO_BUFFER_SIZE: usize = 64 * 1024;
+// default upper bound on the backing buffer that individual chunks are carved
+// out of. each emitted `Bytes` shares this allocation via `split_to`, so we
+// only hit the allocator once per buffer-worth of body rather than once per
+// chunk. callers that want to trade memory against syscall count can override
+// it with [`RangedStream::with_buffer_size`].
+const POOL_BUFFER_SIZE: usize = 256 * 1024;
+
+// size of the first backing buffer. starting small keeps first-byte latency
+// and memory low for tiny ranges; the buffer then doubles on each refill up to
+// the configured maximum so bulk transfers still get large reads.
+const INITIAL_BUFFER_SIZE: usize = 16 * 1024;
 
 #[pin_project]
 pub struct RangedStream<B> {
     state: StreamState,
+    // adaptive backing-buffer sizing, shared by every refill in `Reading`
+    buffer: BufferSize,
     #[pin]
     body: B,
 }
 
 impl<B: RangeBody> RangedStream<B> {
     pub fn new(body: B, start: u64, length: u64) -> Self {
+        Self::with_buffer_size(body, start, length, POOL_BUFFER_SIZE)
+    }
+
+    /// Construct a stream whose backing buffer grows up to `buffer_size` bytes,
+    /// mirroring [`tokio::io::BufReader::with_capacity`]. The maximum is
+    /// clamped to `min(buffer_size, length)` so short ranges never
+    /// over-allocate; the first allocation is the smaller of 16 KiB and that
+    /// maximum, and the buffer then doubles on each refill up to it.
+    pub fn with_buffer_size(body: B, start: u64, length: u64, buffer_size: usize) -> Self {
         RangedStream {
             state: StreamState::Seek { start, remaining: length },
+            buffer: BufferSize::new(buffer_size, length),
             body,
         }
     }
 }
 
+// tracks the adaptive growth of the backing buffer across refills.
+pub(crate) struct BufferSize {
+    // current allocation size, doubled on each refill up to `max`
+    current: usize,
+    // configured upper bound, already clamped to the range length
+    max: usize,
+}
+
+impl BufferSize {
+    pub(crate) fn new(buffer_size: usize, length: u64) -> Self {
+        // never allocate more than the total range length
+        let max = std::cmp::min(
+            buffer_size.max(1),
+            usize::try_from(length).unwrap_or(usize::MAX).max(1),
+        );
+        let current = std::cmp::min(INITIAL_BUFFER_SIZE, max);
+        BufferSize { current, max }
+    }
+
+    // allocate the next backing buffer, then double the size for the one after
+    pub(crate) fn allocate(&mut self) -> BytesMut {
+        let buffer = BytesMut::with_capacity(self.current);
+        self.current = self.current.saturating_mul(2).min(self.max);
+        buffer
+    }
+}
+
+// Read a single chunk of up to `remaining` bytes from `body` into `buffer`,
+// refilling the backing allocation from `sizing` when its spare capacity is
+// exhausted. Returns `Ok(None)` when the body reaches EOF (a zero-length
+// read), otherwise `Ok(Some(chunk))` sharing `buffer`'s allocation. Shared by
+// `RangedStream` and `MultiRangeStream` so the zero-copy read path can't drift
+// between them.
+pub(crate) fn poll_read_chunk<B: RangeBody>(
+    body: Pin<&mut B>,
+    cx: &mut Context<'_>,
+    buffer: &mut BytesMut,
+    sizing: &mut BufferSize,
+    remaining: &mut u64,
+) -> Poll<io::Result<Option<Bytes>>> {
+    // if the backing buffer's spare capacity is exhausted, grab a fresh block.
+    // everything carved off the old one keeps it alive for as long as the
+    // consumer holds the `Bytes`, so apart from the first buffer allocated
+    // when seeking completes, this is the only point at which we touch the
+    // allocator.
+    if buffer.spare_capacity_mut().is_empty() {
+        *buffer = sizing.allocate();
+    }
+
+    let uninit = buffer.spare_capacity_mut();
+
+    // calculate max number of bytes to read in this iteration, the smaller of
+    // the spare capacity and the number of bytes remaining
+    let nbytes = std::cmp::min(
+        uninit.len(),
+        usize::try_from(*remaining).unwrap_or(usize::MAX),
+    );
+
+    let mut read_buf = ReadBuf::uninit(&mut uninit[0..nbytes]);
+
+    match body.poll_read(cx, &mut read_buf) {
+        Poll::Pending => Poll::Pending,
+        Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+        Poll::Ready(Ok(())) => match read_buf.filled().len() {
+            0 => Poll::Ready(Ok(None)),
+            n => {
+                // SAFETY: poll_read has filled the buffer with `n` additional
+                // bytes on top of whatever the previous `split_to` left behind
+                // (always 0 in practice)
+                unsafe { buffer.set_len(buffer.len() + n); }
+
+                // split the freshly-read bytes off the front of the backing
+                // buffer. this shares the allocation rather than copying, and
+                // leaves the remaining spare capacity in place for the next read
+                let chunk = buffer.split_to(n);
+
+                // subtract the number of bytes we just read from remaining,
+                // this usize->u64 conversion is guaranteed to always succeed,
+                // because n cannot be larger than remaining due to the cmp::min
+                *remaining -= u64::try_from(n).unwrap();
+
+                Poll::Ready(Ok(Some(chunk.freeze())))
+            }
+        },
+    }
+}
+
 enum StreamState {
     Seek { start: u64, remaining: u64 },
     Seeking { remaining: u64 },
@@ -54,57 +163,21 @@ impl<B: RangeBody> Stream for RangedStream<B> {
                 Poll::Pending => { return Poll::Pending; }
                 Poll::Ready(Err(e)) => { return Poll::Ready(Some(Err(e))); }
                 Poll::Ready(Ok(())) => {
-                    let buffer = allocate_buffer();
+                    let buffer = this.buffer.allocate();
                     *this.state = StreamState::Reading { buffer, remaining };
                 }
             }
         }
 
         if let StreamState::Reading { buffer, remaining } = this.state {
-            let uninit = buffer.spare_capacity_mut();
-
-            // calculate max number of bytes to read in this iteration, the
-            // smaller of the buffer size and the number of bytes remaining
-            let nbytes = std::cmp::min(
-                uninit.len(),
-                usize::try_from(*remaining).unwrap_or(usize::MAX),
-            );
-
-            let mut read_buf = ReadBuf::uninit(&mut uninit[0..nbytes]);
-
-            match this.body.as_mut().poll_read(cx, &mut read_buf) {
-                Poll::Pending => { return Poll::Pending; }
-                Poll::Ready(Err(e)) => { return Poll::Ready(Some(Err(e))); }
-                Poll::Ready(Ok(())) => {
-                    match read_buf.filled().len() {
-                        0 => { return Poll::Ready(None); }
-                        n => {
-                            // SAFETY: poll_read has filled the buffer with `n`
-                            // additional bytes. `buffer.len` should always be
-                            // 0 here, but include it for rigorous correctness
-                            unsafe { buffer.set_len(buffer.len() + n); }
-
-                            // replace state buffer and take this one to return
-                            let chunk = mem::replace(buffer, allocate_buffer());
-
-                            // subtract the number of bytes we just read from
-                            // state.remaining, this usize->u64 conversion is
-                            // guaranteed to always succeed, because n cannot be
-                            // larger than remaining due to the cmp::min above
-                            *remaining -= u64::try_from(n).unwrap();
-
-                            // return this chunk
-                            return Poll::Ready(Some(Ok(chunk.freeze())));
-                        }
-                    }
-                }
-            }
+            return match poll_read_chunk(this.body, cx, buffer, this.buffer, remaining) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(Err(e)) => Poll::Ready(Some(Err(e))),
+                Poll::Ready(Ok(None)) => Poll::Ready(None),
+                Poll::Ready(Ok(Some(chunk))) => Poll::Ready(Some(Ok(chunk))),
+            };
         }
 
         unreachable!();
     }
-}
-
-fn allocate_buffer() -> BytesMut {
-    BytesMut::with_capacity(IO_BUFFER_SIZE)
 }
\ No newline at end of file
@@ -0,0 +1,136 @@
+//! Forward-only [`RangeBody`] adapter over an arbitrary byte stream.
+//!
+//! Declared in the crate root as `pub mod stream_body;`. Many producers — an
+//! upstream HTTP body, a decompressor, a pipe — are not seekable, yet callers
+//! still want [`crate::Ranged`] to answer `bytes=N-` style ranges against them.
+//! [`StreamRangeBody`] wraps a `Stream<Item = io::Result<Bytes>>` of known
+//! total length and implements enough of [`RangeBody`] for forward-only
+//! ranges: seeking ahead discards inbound bytes until the target offset is
+//! reached; seeking backward relative to what has already been consumed fails
+//! with an [`io::Error`], which is the correct behavior for a source that
+//! cannot rewind.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures::Stream;
+use pin_project::pin_project;
+use tokio::io::ReadBuf;
+
+use crate::RangeBody;
+
+/// Adapts a non-seekable byte stream into a forward-only [`RangeBody`].
+#[pin_project]
+pub struct StreamRangeBody<S> {
+    #[pin]
+    stream: S,
+    size: u64,
+    // absolute offset of the byte at the front of `buffer` (equivalently, the
+    // number of source bytes already consumed or discarded)
+    position: u64,
+    // target offset requested by the most recent `start_seek`
+    target: u64,
+    // inbound bytes pulled from the source but not yet handed to the reader
+    buffer: BytesMut,
+    // the source stream has yielded `None`
+    eof: bool,
+}
+
+impl<S> StreamRangeBody<S> {
+    /// Wrap `stream`, whose bytes total `size`.
+    pub fn new(stream: S, size: u64) -> Self {
+        StreamRangeBody {
+            stream,
+            size,
+            position: 0,
+            target: 0,
+            buffer: BytesMut::new(),
+            eof: false,
+        }
+    }
+}
+
+impl<S> RangeBody for StreamRangeBody<S>
+where
+    S: Stream<Item = io::Result<Bytes>>,
+{
+    fn byte_size(&self) -> u64 {
+        self.size
+    }
+
+    fn start_seek(self: Pin<&mut Self>, position: u64) -> io::Result<()> {
+        let this = self.project();
+        // the front of `buffer` is the earliest byte we can still produce;
+        // anything before it has been discarded and cannot be recovered
+        if position < *this.position {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "StreamRangeBody cannot seek backwards over a non-seekable source",
+            ));
+        }
+        *this.target = position;
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+
+        // discard inbound bytes until the source cursor reaches the target
+        while *this.position < *this.target {
+            // pull more only when we have nothing left to discard; an empty
+            // inbound chunk makes no progress, so keep polling rather than
+            // looping on a zero-length `skip`
+            while this.buffer.is_empty() {
+                match this.stream.as_mut().poll_next(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(None) => {
+                        // reached EOF before the target; reads will yield nothing
+                        *this.eof = true;
+                        return Poll::Ready(Ok(()));
+                    }
+                    Poll::Ready(Some(Err(e))) => return Poll::Ready(Err(e)),
+                    Poll::Ready(Some(Ok(bytes))) => this.buffer.extend_from_slice(&bytes),
+                }
+            }
+
+            let skip = std::cmp::min(
+                this.buffer.len() as u64,
+                *this.target - *this.position,
+            );
+            this.buffer.advance(skip as usize);
+            *this.position += skip;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+
+        // top up from the source if we have nothing buffered to hand out.
+        // keep polling past empty chunks: a zero-length `buf` fill is read as
+        // EOF by `RangedStream`, so we must not return one until the source
+        // genuinely ends
+        while this.buffer.is_empty() && !*this.eof {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => *this.eof = true,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Err(e)),
+                Poll::Ready(Some(Ok(bytes))) => this.buffer.extend_from_slice(&bytes),
+            }
+        }
+
+        let n = std::cmp::min(buf.remaining(), this.buffer.len());
+        buf.put_slice(&this.buffer[..n]);
+        this.buffer.advance(n);
+        *this.position += n as u64;
+
+        Poll::Ready(Ok(()))
+    }
+}
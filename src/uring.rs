@@ -0,0 +1,134 @@
+//! io_uring-backed [`RangeBody`] for high-throughput file serving.
+//!
+//! This module is gated behind the `uring` cargo feature and declared in the
+//! crate root as:
+//!
+//! ```ignore
+//! #[cfg(feature = "uring")]
+//! pub mod uring;
+//! ```
+//!
+//! Unlike [`tokio::fs::File`], which dispatches every read to a blocking
+//! thread-pool task, [`UringFileRangeBody`] keeps an owned
+//! [`tokio_uring::fs::File`] and submits positional `read_at(buf, offset)`
+//! operations directly to the ring. Because io_uring reads are positional, the
+//! seek machinery collapses to tracking the current offset: `start_seek` only
+//! records it and `poll_complete` is always immediately ready.
+
+use std::future::Future;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, BytesMut};
+use tokio::io::ReadBuf;
+use tokio_uring::fs::File;
+
+use crate::RangeBody;
+
+// io_uring reads hand back ownership of the submitted buffer on completion.
+type PendingRead = Pin<Box<dyn Future<Output = (io::Result<usize>, Vec<u8>)>>>;
+
+/// A [`RangeBody`] served from an owned [`tokio_uring::fs::File`].
+pub struct UringFileRangeBody {
+    // shared so each in-flight read future can own a handle without borrowing
+    // `self`, keeping the future `'static`
+    file: Rc<File>,
+    size: u64,
+    offset: u64,
+    pending: Option<PendingRead>,
+    // read bytes that didn't fit the caller's `ReadBuf` and are handed out on
+    // the next `poll_read` before any new submission
+    leftover: BytesMut,
+    // reusable submission buffer, recovered from each completed read so we
+    // don't allocate per read
+    scratch: Vec<u8>,
+}
+
+impl UringFileRangeBody {
+    /// Wrap an already-opened file of known `size`.
+    pub fn new(file: File, size: u64) -> Self {
+        UringFileRangeBody {
+            file: Rc::new(file),
+            size,
+            offset: 0,
+            pending: None,
+            leftover: BytesMut::new(),
+            scratch: Vec::new(),
+        }
+    }
+}
+
+impl RangeBody for UringFileRangeBody {
+    fn byte_size(&self) -> u64 {
+        self.size
+    }
+
+    fn start_seek(self: Pin<&mut Self>, position: u64) -> io::Result<()> {
+        // positional reads mean "seeking" is just recording where to read from
+        self.get_mut().offset = position;
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // nothing is in flight for a seek; the offset is already set
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        // hand out anything a previous, larger read left buffered before
+        // submitting a new one
+        if !this.leftover.is_empty() {
+            let n = std::cmp::min(buf.remaining(), this.leftover.len());
+            buf.put_slice(&this.leftover[..n]);
+            this.leftover.advance(n);
+            return Poll::Ready(Ok(()));
+        }
+
+        // submit a read for the current offset if one isn't already running,
+        // reusing the recovered scratch buffer to avoid a per-read allocation
+        if this.pending.is_none() {
+            let file = Rc::clone(&this.file);
+            let offset = this.offset;
+            let mut scratch = mem::take(&mut this.scratch);
+            scratch.clear();
+            scratch.resize(buf.remaining(), 0);
+            this.pending = Some(Box::pin(async move {
+                file.read_at(scratch, offset).await
+            }));
+        }
+
+        let future = this.pending.as_mut().expect("pending read just set");
+
+        match future.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready((result, scratch)) => {
+                this.pending = None;
+                // recover the buffer for the next submission
+                this.scratch = scratch;
+                match result {
+                    Ok(n) => {
+                        // the caller's `ReadBuf` may have shrunk since we sized
+                        // the submission; copy what fits and stash the rest
+                        let take = std::cmp::min(n, buf.remaining());
+                        buf.put_slice(&this.scratch[..take]);
+                        if take < n {
+                            this.leftover.extend_from_slice(&this.scratch[take..n]);
+                        }
+                        this.offset += n as u64;
+                        Poll::Ready(Ok(()))
+                    }
+                    Err(e) => Poll::Ready(Err(e)),
+                }
+            }
+        }
+    }
+}